@@ -18,10 +18,15 @@ pub struct Args {
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
-    #[command(about = "Build a container image for data engineering projects. Uses BuildKit Providerless build.")]
+    #[command(about = "Build a container image for data engineering projects. Uses BuildKit Providerless build. \
+        Note: BuildKit secret mounts (`--secret`) are not supported yet - bollard's build client has no way to \
+        serve the gRPC secrets session they need.")]
     Build {
         #[arg(default_value = ".", help = "Directory with the Dockerfile to build")]
         path: PathBuf,
+
+        #[arg(long = "build-arg", help = "Build-time variable, e.g. KEY=VALUE (repeatable)")]
+        build_arg: Vec<String>,
     },
     #[command(about = "Build, run, and test a container image (cleans up afterwards)")]
     Test {
@@ -30,6 +35,37 @@ pub enum Command {
 
         #[arg(long, help = "Start an interactive bash session instead of running the default command")]
         bash: bool,
+
+        #[arg(long, help = "Run non-interactively for CI: no TTY, demuxed output, exit code mirrors the container's")]
+        ci: bool,
+
+        #[arg(long, help = "Host directory to mount into the container at /data (bind-mounted locally, synced via a volume for a remote engine)")]
+        data: Option<PathBuf>,
+    },
+    #[command(about = "Push a built image to a remote registry")]
+    Push {
+        #[arg(default_value = ".", help = "Directory with the image to push")]
+        path: PathBuf,
+
+        #[arg(help = "Registry (and optional org/path) to push to, e.g. ghcr.io/org")]
+        registry: String,
+
+        #[arg(long, help = "Tag to push as (defaults to the local build's tag)")]
+        tag: Option<String>,
+
+        #[arg(long, env = "DOCKER_USERNAME", help = "Registry username (falls back to ~/.docker/config.json)")]
+        username: Option<String>,
+
+        #[arg(long, env = "DOCKER_PASSWORD", help = "Registry password (falls back to ~/.docker/config.json)")]
+        password: Option<String>,
+    },
+    #[command(about = "Run a command inside the running test container")]
+    Exec {
+        #[arg(default_value = ".", help = "Directory the test container was built from")]
+        path: PathBuf,
+
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true, help = "Command (and arguments) to run inside the container")]
+        cmd: Vec<String>,
     },
 }
 
@@ -2,7 +2,9 @@ use bollard::Docker;
 use bollard::query_parameters::BuildImageOptionsBuilder;
 use bollard::query_parameters::BuilderVersion;
 use bollard::models::BuildInfoAux;
-use std::path::Path;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use futures_util::stream::StreamExt;
 use http_body_util::{Either, Full};
 use bytes::Bytes;
@@ -15,30 +17,51 @@ pub fn get_image_tag(path: &Path) -> String {
     format!("{}:test", folder_name)
 }
 
-pub async fn build_image(path: &Path, verbose: bool) -> Result<String, Box<dyn std::error::Error>> {
+// So `dect exec` can find the container `dect test` built without tracking container ids.
+pub fn get_container_name(path: &Path) -> String {
+    let absolute_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let folder_name = absolute_path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("dataeng");
+    format!("dect-test-{}", folder_name)
+}
+
+// No BuildKit secret mount (`--secret`) support: bollard's build client speaks the plain HTTP
+// build API and has no way to serve the gRPC secrets session `RUN --mount=type=secret` needs.
+pub async fn build_image(
+    path: &Path,
+    build_args: &[String],
+    verbose: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
     let docker = Docker::connect_with_local_defaults()?;
-    
+
     println!("Building image from: {}", path.display());
-    
+
+    // Expand any `INCLUDE+ <path>` directives before the Dockerfile goes into the build context
+    let dockerfile = expand_dockerfile(&path.join("Dockerfile"))?;
+
     // Create a compressed tar archive of the build context
-    let tar = create_tar_archive(path)?;
-    
+    let tar = create_tar_archive(path, &dockerfile)?;
+
     let image_tag = get_image_tag(path);
-    
+
+    let buildargs = parse_build_args(build_args)?;
+
     let options = BuildImageOptionsBuilder::default()
         .t(&image_tag)
         .rm(true)
         .dockerfile("Dockerfile")
         .version(BuilderVersion::BuilderBuildKit)
         .session(&image_tag)
+        .buildargs(&buildargs)
         .build();
-    
+
     let mut stream = docker.build_image(
         options,
         None,
         Some(Either::Left(Full::new(Bytes::from(tar))))
     );
-    
+
     while let Some(msg) = stream.next().await {
         match msg {
             Ok(output) => {
@@ -55,22 +78,76 @@ pub async fn build_image(path: &Path, verbose: bool) -> Result<String, Box<dyn s
             Err(e) => return Err(e.into()),
         }
     }
-    
+
     println!("\n✓ Image built successfully: {}", image_tag);
     Ok(image_tag)
 }
 
-fn create_tar_archive(path: &Path) -> Result<Vec<u8>, std::io::Error> {
+fn parse_build_args(build_args: &[String]) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    build_args.iter().map(|arg| {
+        let (key, value) = arg.split_once('=')
+            .ok_or_else(|| format!("invalid --build-arg '{}', expected KEY=VALUE", arg))?;
+        Ok((key.to_string(), value.to_string()))
+    }).collect()
+}
+
+fn create_tar_archive(path: &Path, dockerfile: &str) -> Result<Vec<u8>, std::io::Error> {
     use std::io::Write;
-    
+
     let mut tar = tar::Builder::new(Vec::new());
     tar.append_dir_all(".", path)?;
+
+    // Append the expanded Dockerfile last so it wins over the raw (still `INCLUDE+`-bearing)
+    // copy that `append_dir_all` just archived from disk; the daemon only sees this version.
+    let contents = dockerfile.as_bytes();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, "Dockerfile", contents)?;
+
     tar.finish()?;
-    
+
     let uncompressed = tar.into_inner()?;
     let mut c = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
     c.write_all(&uncompressed)?;
     let compressed = c.finish()?;
-    
+
     Ok(compressed)
 }
+
+// Recursively expand `INCLUDE+ <path>` directives, relative to the including file's directory.
+fn expand_dockerfile(path: &Path) -> Result<String, std::io::Error> {
+    let mut chain = HashSet::new();
+    expand_dockerfile_inner(path, &mut chain)
+}
+
+fn expand_dockerfile_inner(path: &Path, chain: &mut HashSet<PathBuf>) -> Result<String, std::io::Error> {
+    let canonical = path.canonicalize()?;
+    if !chain.insert(canonical.clone()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("INCLUDE+ cycle detected at {}", path.display()),
+        ));
+    }
+
+    let include_re = Regex::new(r"^\s*INCLUDE\+\s+(\S+)\s*$").unwrap();
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut expanded = String::new();
+
+    for line in std::fs::read_to_string(path)?.lines() {
+        match include_re.captures(line) {
+            Some(caps) => {
+                let include_path = dir.join(&caps[1]);
+                expanded.push_str(&expand_dockerfile_inner(&include_path, chain)?);
+            }
+            None => {
+                expanded.push_str(line);
+                expanded.push('\n');
+            }
+        }
+    }
+
+    chain.remove(&canonical);
+    Ok(expanded)
+}
@@ -0,0 +1,162 @@
+use bollard::models::{ContainerCreateBody, HostConfig};
+use bollard::query_parameters::{
+    CreateContainerOptions, CreateImageOptionsBuilder, CreateVolumeOptions,
+    DownloadFromContainerOptionsBuilder, RemoveContainerOptionsBuilder,
+    RemoveVolumeOptionsBuilder, UploadToContainerOptionsBuilder,
+};
+use bollard::Docker;
+use futures_util::stream::StreamExt;
+use std::env;
+use std::path::Path;
+
+// Where `--data` is mounted inside the test container.
+pub const CONTAINER_DATA_PATH: &str = "/data";
+
+// Helper container image used to populate a named volume via `docker cp`; results are copied
+// back out of the test container itself (see `sync_back`).
+const HELPER_IMAGE: &str = "busybox:latest";
+
+// A direct bind for a local engine, or a named volume (populated from the host via a helper
+// container) for a remote one.
+pub enum DataMount {
+    Bind(String),
+    Volume(String),
+}
+
+impl DataMount {
+    pub fn source(&self) -> &str {
+        match self {
+            DataMount::Bind(path) => path,
+            DataMount::Volume(name) => name,
+        }
+    }
+}
+
+// A remote engine can't resolve a host path, so `--data` needs a named volume instead.
+fn is_remote_engine() -> bool {
+    env::var("DOCKER_HOST")
+        .map(|host| {
+            host.starts_with("tcp://") || host.starts_with("http://") || host.starts_with("https://") || host.starts_with("ssh://")
+        })
+        .unwrap_or(false)
+}
+
+pub async fn provision(
+    docker: &Docker,
+    host_dir: &Path,
+    verbose: bool,
+) -> Result<DataMount, Box<dyn std::error::Error>> {
+    let host_dir = host_dir.canonicalize()?;
+
+    if !is_remote_engine() {
+        if verbose {
+            println!("Local engine: bind-mounting {}", host_dir.display());
+        }
+        return Ok(DataMount::Bind(host_dir.to_string_lossy().to_string()));
+    }
+
+    let volume_name = format!("dect-data-{}", unique_suffix());
+    if verbose {
+        println!("Remote engine: provisioning volume {} from {}", volume_name, host_dir.display());
+    }
+
+    docker.create_volume(CreateVolumeOptions {
+        name: Some(volume_name.clone()),
+        ..Default::default()
+    }).await?;
+
+    if let Err(e) = populate_volume(docker, &volume_name, &host_dir).await {
+        let remove_options = RemoveVolumeOptionsBuilder::default().force(true).build();
+        docker.remove_volume(&volume_name, Some(remove_options)).await.ok();
+        return Err(e);
+    }
+
+    Ok(DataMount::Volume(volume_name))
+}
+
+// No-op for a plain bind mount, since the host directory was already being written to directly.
+pub async fn sync_back(
+    docker: &Docker,
+    mount: &DataMount,
+    container_id: &str,
+    host_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let DataMount::Volume(volume_name) = mount else {
+        return Ok(());
+    };
+
+    // Trailing `/.` tells Docker's archive semantics to root the tar at the directory's
+    // *contents*, not at a `data/` entry, so unpacking lands straight into `host_dir`.
+    let options = DownloadFromContainerOptionsBuilder::default()
+        .path(&format!("{}/.", CONTAINER_DATA_PATH))
+        .build();
+    let mut stream = docker.download_from_container(container_id, Some(options));
+    let mut tar_bytes = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        tar_bytes.extend_from_slice(&chunk?);
+    }
+    tar::Archive::new(std::io::Cursor::new(tar_bytes)).unpack(host_dir)?;
+
+    let remove_options = RemoveVolumeOptionsBuilder::default().force(true).build();
+    docker.remove_volume(volume_name, Some(remove_options)).await?;
+
+    Ok(())
+}
+
+async fn populate_volume(
+    docker: &Docker,
+    volume_name: &str,
+    host_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    pull_helper_image(docker).await?;
+
+    let config = ContainerCreateBody {
+        image: Some(HELPER_IMAGE.to_string()),
+        cmd: Some(vec!["true".to_string()]),
+        host_config: Some(HostConfig {
+            binds: Some(vec![format!("{}:{}", volume_name, CONTAINER_DATA_PATH)]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let helper = docker.create_container(None::<CreateContainerOptions>, config).await?;
+    let helper_id = helper.id.clone();
+
+    let tar = create_tar_archive(host_dir)?;
+    let upload_options = UploadToContainerOptionsBuilder::default()
+        .path(CONTAINER_DATA_PATH)
+        .build();
+
+    let upload_result = docker.upload_to_container(&helper_id, Some(upload_options), tar.into()).await;
+
+    let remove_options = RemoveContainerOptionsBuilder::default().force(true).build();
+    docker.remove_container(&helper_id, Some(remove_options)).await.ok();
+
+    upload_result?;
+    Ok(())
+}
+
+// A remote engine has no reason to already have HELPER_IMAGE cached.
+async fn pull_helper_image(docker: &Docker) -> Result<(), Box<dyn std::error::Error>> {
+    let create_image_options = CreateImageOptionsBuilder::default()
+        .from_image(HELPER_IMAGE)
+        .build();
+    let mut stream = docker.create_image(Some(create_image_options), None, None);
+    while let Some(msg) = stream.next().await {
+        msg?;
+    }
+    Ok(())
+}
+
+fn create_tar_archive(path: &Path) -> Result<Vec<u8>, std::io::Error> {
+    let mut tar = tar::Builder::new(Vec::new());
+    tar.append_dir_all(".", path)?;
+    tar.into_inner()
+}
+
+fn unique_suffix() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    format!("{:x}", nanos)
+}
@@ -0,0 +1,50 @@
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::query_parameters::StartExecOptions;
+use bollard::Docker;
+use futures_util::stream::StreamExt;
+use std::io::Write;
+use std::path::Path;
+
+use super::get_container_name;
+
+pub async fn exec_command(path: &Path, cmd: Vec<String>) -> Result<i32, Box<dyn std::error::Error>> {
+    let docker = Docker::connect_with_local_defaults()?;
+    let container_name = get_container_name(path);
+    exec_in_container(&docker, &container_name, cmd).await
+}
+
+pub async fn exec_in_container(
+    docker: &Docker,
+    container_id: &str,
+    cmd: Vec<String>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let exec = docker.create_exec(container_id, CreateExecOptions {
+        cmd: Some(cmd),
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        ..Default::default()
+    }).await?;
+
+    if let StartExecResults::Attached { mut output, .. } = docker.start_exec(&exec.id, None::<StartExecOptions>).await? {
+        let mut stdout = std::io::stdout();
+        let mut stderr = std::io::stderr();
+
+        while let Some(frame) = output.next().await {
+            match frame? {
+                LogOutput::StdOut { message } => {
+                    stdout.write_all(&message)?;
+                    stdout.flush()?;
+                }
+                LogOutput::StdErr { message } => {
+                    stderr.write_all(&message)?;
+                    stderr.flush()?;
+                }
+                LogOutput::StdIn { .. } | LogOutput::Console { .. } => {}
+            }
+        }
+    }
+
+    let inspect = docker.inspect_exec(&exec.id).await?;
+    Ok(inspect.exit_code.unwrap_or(0) as i32)
+}
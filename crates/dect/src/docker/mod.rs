@@ -1,5 +1,10 @@
 pub mod build;
+pub mod data;
+pub mod exec;
+pub mod push;
 pub mod test;
 
-pub use build::{build_image, get_image_tag};
+pub use build::{build_image, get_container_name, get_image_tag};
+pub use exec::{exec_command, exec_in_container};
+pub use push::push_image;
 pub use test::test_container;
@@ -0,0 +1,137 @@
+use base64::Engine;
+use bollard::auth::DockerCredentials;
+use bollard::query_parameters::{PushImageOptionsBuilder, TagImageOptionsBuilder};
+use bollard::Docker;
+use futures_util::stream::StreamExt;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use super::get_image_tag;
+
+// Credentials are resolved, in order, from --username/--password (or their env vars), then
+// ~/.docker/config.json.
+pub async fn push_image(
+    path: &Path,
+    registry: &str,
+    tag: Option<&str>,
+    username: Option<String>,
+    password: Option<String>,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let docker = Docker::connect_with_local_defaults()?;
+
+    let local_tag = get_image_tag(path);
+    let (repo, default_tag) = local_tag
+        .rsplit_once(':')
+        .unwrap_or((local_tag.as_str(), "test"));
+    let remote_repo = format!("{}/{}", registry.trim_end_matches('/'), repo);
+    let remote_tag = tag.unwrap_or(default_tag);
+    let remote_image = format!("{}:{}", remote_repo, remote_tag);
+
+    let tag_options = TagImageOptionsBuilder::default()
+        .repo(&remote_repo)
+        .tag(remote_tag)
+        .build();
+    docker.tag_image(&local_tag, Some(tag_options)).await?;
+
+    // `docker login` stores auth entries under the bare host, so strip any `/org/path` suffix
+    // before looking credentials up (or filling in `serveraddress`) - keep the untrimmed
+    // `registry` above for building `remote_repo`.
+    let registry_host = registry.split('/').next().unwrap_or(registry);
+    let credentials = resolve_credentials(registry_host, username, password)?;
+
+    println!("Pushing image: {}", remote_image);
+
+    let push_options = PushImageOptionsBuilder::default().tag(remote_tag).build();
+    let mut stream = docker.push_image(&remote_repo, Some(push_options), Some(credentials));
+
+    while let Some(msg) = stream.next().await {
+        match msg {
+            Ok(info) => {
+                if verbose {
+                    if let Some(status) = info.status {
+                        println!("{}", status);
+                    }
+                }
+                if let Some(error) = info.error {
+                    eprintln!("Push error: {}", error);
+                    return Err(error.into());
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    println!("\n✓ Image pushed successfully: {}", remote_image);
+    Ok(())
+}
+
+fn resolve_credentials(
+    registry: &str,
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<DockerCredentials, Box<dyn std::error::Error>> {
+    if let (Some(username), Some(password)) = (&username, &password) {
+        return Ok(DockerCredentials {
+            username: Some(username.clone()),
+            password: Some(password.clone()),
+            serveraddress: Some(registry.to_string()),
+            ..Default::default()
+        });
+    }
+
+    if let Some(credentials) = credentials_from_docker_config(registry)? {
+        return Ok(credentials);
+    }
+
+    Err(format!(
+        "No credentials found for registry '{}'; pass --username/--password or log in with `docker login`",
+        registry
+    )
+    .into())
+}
+
+fn docker_config_path() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("DOCKER_CONFIG") {
+        return Some(PathBuf::from(dir).join("config.json"));
+    }
+    #[cfg(not(windows))]
+    let home = env::var("HOME").ok()?;
+    #[cfg(windows)]
+    let home = env::var("USERPROFILE").ok()?;
+    Some(PathBuf::from(home).join(".docker").join("config.json"))
+}
+
+fn credentials_from_docker_config(
+    registry: &str,
+) -> Result<Option<DockerCredentials>, Box<dyn std::error::Error>> {
+    let Some(config_path) = docker_config_path() else {
+        return Ok(None);
+    };
+    let Ok(contents) = std::fs::read_to_string(&config_path) else {
+        return Ok(None);
+    };
+
+    let config: serde_json::Value = serde_json::from_str(&contents)?;
+    let Some(auth_entry) = config
+        .get("auths")
+        .and_then(|auths| auths.get(registry))
+        .and_then(|entry| entry.get("auth"))
+        .and_then(|auth| auth.as_str())
+    else {
+        return Ok(None);
+    };
+
+    let decoded = base64::engine::general_purpose::STANDARD.decode(auth_entry)?;
+    let decoded = String::from_utf8(decoded)?;
+    let Some((username, password)) = decoded.split_once(':') else {
+        return Ok(None);
+    };
+
+    Ok(Some(DockerCredentials {
+        username: Some(username.to_string()),
+        password: Some(password.to_string()),
+        serveraddress: Some(registry.to_string()),
+        ..Default::default()
+    }))
+}
@@ -1,13 +1,15 @@
+use bollard::container::LogOutput;
 use bollard::Docker;
-use bollard::models::ContainerCreateBody;
-use bollard::query_parameters::{CreateContainerOptions, RemoveContainerOptionsBuilder, AttachContainerOptionsBuilder, RemoveImageOptionsBuilder, StartContainerOptions};
+use bollard::models::{ContainerCreateBody, HostConfig};
+use bollard::query_parameters::{CreateContainerOptions, RemoveContainerOptionsBuilder, AttachContainerOptionsBuilder, RemoveImageOptionsBuilder, StartContainerOptions, WaitContainerOptions};
 use futures_util::stream::StreamExt;
+use std::io::Write;
 use std::path::Path;
 use tokio::io::AsyncWriteExt;
 use tokio::task::spawn;
 
 #[cfg(not(windows))]
-use std::io::{stdout, Read, Write};
+use std::io::{stdout, Read};
 #[cfg(not(windows))]
 use termion::async_stdin;
 #[cfg(not(windows))]
@@ -17,46 +19,73 @@ use tokio::time::sleep;
 #[cfg(not(windows))]
 use std::time::Duration;
 
-use super::{build_image, get_image_tag};
+use super::data::{self, CONTAINER_DATA_PATH};
+use super::{build_image, get_container_name, get_image_tag};
 
-pub async fn test_container(path: &Path, bash: bool, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn test_container(path: &Path, bash: bool, ci: bool, data_dir: Option<&Path>, verbose: bool) -> Result<i32, Box<dyn std::error::Error>> {
     let docker = Docker::connect_with_local_defaults()?;
-    
+
     // Build the image first
-    build_image(path, verbose).await?;
-    
+    build_image(path, &[], verbose).await?;
+
     // Get the image tag that was built
     let image_tag = get_image_tag(path);
-    
+
     println!("\nStarting container for testing...");
-    
+
+    // Resolve --data into a bind (local engine) or a populated named volume (remote engine).
+    // Provisioning can fail after the image has already been built, so remove it on that path
+    // too rather than leaking it.
+    let data_mount = match data_dir {
+        Some(dir) => match data::provision(&docker, dir, verbose).await {
+            Ok(mount) => Some(mount),
+            Err(e) => {
+                let remove_image_options = RemoveImageOptionsBuilder::default().force(true).build();
+                docker.remove_image(&image_tag, Some(remove_image_options), None).await.ok();
+                return Err(e);
+            }
+        },
+        None => None,
+    };
+
     // Configure container
     let mut config = ContainerCreateBody {
         image: Some(image_tag.clone()),
-        tty: Some(true),
+        tty: Some(!ci),
         attach_stdout: Some(true),
         attach_stderr: Some(true),
-        open_stdin: Some(true),
-        attach_stdin: Some(true),
+        open_stdin: Some(!ci),
+        attach_stdin: Some(!ci),
         ..Default::default()
     };
-    
+
+    if let Some(mount) = &data_mount {
+        config.host_config = Some(HostConfig {
+            binds: Some(vec![format!("{}:{}", mount.source(), CONTAINER_DATA_PATH)]),
+            ..Default::default()
+        });
+    }
+
     // Override with bash if requested
     if bash {
         config.entrypoint = Some(vec!["/bin/bash".to_string()]);
         config.cmd = Some(vec![]);
         println!("Interactive bash mode enabled");
     }
-    
-    let container = docker.create_container(None::<CreateContainerOptions>, config).await?;
+
+    let create_options = CreateContainerOptions {
+        name: Some(get_container_name(path)),
+        ..Default::default()
+    };
+    let container = docker.create_container(Some(create_options), config).await?;
     let container_id = container.id.clone();
-    
+
     println!("✓ Container created: {}", container_id);
-    
+
     // Cleanup function
     let cleanup = || async {
         println!("\nCleaning up...");
-        
+
         // Remove container
         let remove_options = RemoveContainerOptionsBuilder::default()
             .force(true)
@@ -66,7 +95,7 @@ pub async fn test_container(path: &Path, bash: bool, verbose: bool) -> Result<()
         } else {
             println!("✓ Container removed");
         }
-        
+
         // Remove image
         let remove_image_options = RemoveImageOptionsBuilder::default()
             .force(true)
@@ -77,24 +106,44 @@ pub async fn test_container(path: &Path, bash: bool, verbose: bool) -> Result<()
             println!("✓ Image removed");
         }
     };
-    
+
     // Start and attach to container
     docker.start_container(&container_id, None::<StartContainerOptions>).await?;
     println!("✓ Container started");
-    
-    // Interactive mode with proper TTY handling
+
+    let result = if ci {
+        run_ci_session(&docker, &container_id).await
+    } else {
+        run_interactive_session(&docker, &container_id).await.map(|_| 0)
+    };
+
+    // Copy results back out of a remote engine's volume before it (and the container) go away
+    if let (Some(mount), Some(dir)) = (&data_mount, data_dir) {
+        if let Err(e) = data::sync_back(&docker, mount, &container_id, dir).await {
+            eprintln!("Warning: Failed to sync --data results back to {}: {}", dir.display(), e);
+        }
+    }
+
+    // Cleanup runs whether the session succeeded or failed
+    cleanup().await;
+
+    result
+}
+
+// Attach with a TTY and pipe the local terminal's stdin/stdout through, like `docker run -it`.
+async fn run_interactive_session(docker: &Docker, container_id: &str) -> Result<(), Box<dyn std::error::Error>> {
     let attach_options = AttachContainerOptionsBuilder::default()
         .stdout(true)
         .stderr(true)
         .stdin(true)
         .stream(true)
         .build();
-    
+
     let bollard::container::AttachContainerResults {
         mut output,
         mut input,
-    } = docker.attach_container(&container_id, Some(attach_options)).await?;
-    
+    } = docker.attach_container(container_id, Some(attach_options)).await?;
+
     #[cfg(not(windows))]
     {
         // Pipe stdin into the docker attach stream input (Unix-like systems with termion)
@@ -109,23 +158,23 @@ pub async fn test_container(path: &Path, bash: bool, verbose: bool) -> Result<()
                 }
             }
         });
-        
+
         // Set stdout in raw mode for TTY
         let stdout = stdout();
         let mut stdout = stdout.lock().into_raw_mode()?;
-        
+
         // Pipe docker attach output into stdout
         while let Some(Ok(output)) = output.next().await {
             stdout.write_all(output.into_bytes().as_ref())?;
             stdout.flush()?;
         }
     }
-    
+
     #[cfg(windows)]
     {
         // Windows: use tokio stdin without raw mode (termion not available)
         use tokio::io::{AsyncReadExt, stdin};
-        
+
         spawn(async move {
             let mut stdin = stdin();
             let mut buf = [0u8; 1];
@@ -135,18 +184,56 @@ pub async fn test_container(path: &Path, bash: bool, verbose: bool) -> Result<()
                 }
             }
         });
-        
+
         // Pipe docker attach output into stdout
-        use std::io::{stdout, Write};
+        use std::io::stdout;
         let mut stdout = stdout();
         while let Some(Ok(output)) = output.next().await {
             stdout.write_all(output.into_bytes().as_ref())?;
             stdout.flush()?;
         }
     }
-    
-    // Cleanup
-    cleanup().await;
-    
+
     Ok(())
 }
+
+// Non-interactive mode for CI: no TTY, so Docker demuxes stdout/stderr for us; wait for the
+// container to exit and return its status code as our exit code.
+async fn run_ci_session(docker: &Docker, container_id: &str) -> Result<i32, Box<dyn std::error::Error>> {
+    let attach_options = AttachContainerOptionsBuilder::default()
+        .stdout(true)
+        .stderr(true)
+        .stream(true)
+        .build();
+
+    let bollard::container::AttachContainerResults { mut output, .. } =
+        docker.attach_container(container_id, Some(attach_options)).await?;
+
+    let mut stdout = std::io::stdout();
+    let mut stderr = std::io::stderr();
+
+    while let Some(frame) = output.next().await {
+        match frame? {
+            LogOutput::StdOut { message } => {
+                stdout.write_all(&message)?;
+                stdout.flush()?;
+            }
+            LogOutput::StdErr { message } => {
+                stderr.write_all(&message)?;
+                stderr.flush()?;
+            }
+            LogOutput::StdIn { .. } | LogOutput::Console { .. } => {}
+        }
+    }
+
+    let mut wait_stream = docker.wait_container(container_id, None::<WaitContainerOptions>);
+    let status_code = match wait_stream.next().await {
+        Some(Ok(response)) => response.status_code,
+        // bollard reports a non-zero exit as an error that carries the status code
+        Some(Err(bollard::errors::Error::DockerContainerWaitError { code, .. })) => code,
+        Some(Err(e)) => return Err(e.into()),
+        None => 0,
+    };
+
+    Ok(status_code as i32)
+}
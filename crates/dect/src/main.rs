@@ -11,19 +11,29 @@ async fn main() {
     let args = Args::parse();
 
     let result = match args.command {
-        Command::Build { path } => {
-            docker::build_image(&path, args.global_options.verbose).await.map(|_| ())
+        Command::Build { path, build_arg } => {
+            docker::build_image(&path, &build_arg, args.global_options.verbose).await.map(|_| 0)
         }
-        Command::Test { path, bash } => {
-            docker::test_container(&path, bash, args.global_options.verbose).await
+        Command::Test { path, bash, ci, data } => {
+            docker::test_container(&path, bash, ci, data.as_deref(), args.global_options.verbose).await
+        }
+        Command::Push { path, registry, tag, username, password } => {
+            docker::push_image(&path, &registry, tag.as_deref(), username, password, args.global_options.verbose).await.map(|_| 0)
+        }
+        Command::Exec { path, cmd } => {
+            docker::exec_command(&path, cmd).await
         }
         Command::ArgparseMd { python_file } => {
-            args_md::generate_markdown(&python_file, args.global_options.verbose).await
+            args_md::generate_markdown(&python_file, args.global_options.verbose).await.map(|_| 0)
         }
     };
-    
-    if let Err(e) = result {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
+
+    match result {
+        Ok(code) if code != 0 => std::process::exit(code),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     }
 }